@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How long a cached entry may be served before it is considered stale and
+/// re-fetched from upstream.
+///
+/// DANDI draft versions are mutable, so anything fetched from a `draft`
+/// endpoint may change out from under us and is only cached briefly.
+/// Published versions (`releases/{version}`) are immutable once minted, so
+/// their pages, assets, and S3 listings can be cached aggressively.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Ttl {
+    /// Time-to-live for mutable resources (draft versions, listings that can
+    /// change).
+    Mutable,
+    /// Time-to-live for immutable resources (published versions and their
+    /// contents).
+    Immutable,
+}
+
+/// The two time-to-live durations a [`Cache`] applies, one for mutable
+/// resources and one for immutable ones.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct CacheConfig {
+    pub(crate) capacity: NonZeroUsize,
+    pub(crate) mutable_ttl: Duration,
+    pub(crate) immutable_ttl: Duration,
+}
+
+impl CacheConfig {
+    fn duration(&self, ttl: Ttl) -> Duration {
+        match ttl {
+            Ttl::Mutable => self.mutable_ttl,
+            Ttl::Immutable => self.immutable_ttl,
+        }
+    }
+}
+
+/// A bounded, TTL'd, LRU cache keyed by the normalized request [`Url`].
+///
+/// Values are stored behind an `Arc` so that hits are cheap to clone out of
+/// the cache without holding the lock while the caller uses them. Recency is
+/// tracked per entry with an atomic access counter, so a hit only needs a read
+/// lock yet still updates LRU ordering. Capacity is bounded: when a fresh key
+/// arrives and the cache is full, the least-recently-*accessed* entry is
+/// evicted, so memory stays flat regardless of how many distinct URLs are
+/// requested over the lifetime of the process.
+#[derive(Debug)]
+pub(crate) struct Cache<T> {
+    config: CacheConfig,
+    /// Monotonic access counter used to stamp entries on each hit/insert.
+    clock: AtomicU64,
+    entries: RwLock<HashMap<Url, Entry<T>>>,
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    /// The instant at which this entry expires.
+    expires: Instant,
+    /// The value of `clock` at this entry's most recent access, used to pick
+    /// the least-recently-used eviction victim.
+    used: AtomicU64,
+    value: Arc<T>,
+}
+
+impl<T> Cache<T> {
+    /// Create an empty cache with the given capacity and TTLs.
+    pub(crate) fn new(config: CacheConfig) -> Cache<T> {
+        Cache {
+            config,
+            clock: AtomicU64::new(0),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached value for `url` if one is present and has not expired,
+    /// marking it as the most recently used entry.
+    ///
+    /// The common (hit) path only takes a read lock and clones out an `Arc`;
+    /// recency is updated through the entry's atomic counter. The write lock is
+    /// taken solely to evict an entry discovered to be stale.
+    pub(crate) fn get(&self, url: &Url) -> Option<Arc<T>> {
+        {
+            let entries = self.entries.read().expect("cache lock should not be poisoned");
+            match entries.get(url) {
+                Some(entry) if entry.expires > Instant::now() => {
+                    entry.used.store(self.tick(), Ordering::Relaxed);
+                    return Some(Arc::clone(&entry.value));
+                }
+                Some(_) => {}
+                None => return None,
+            }
+        }
+        // The entry exists but has expired; drop it under a write lock.
+        let mut entries = self.entries.write().expect("cache lock should not be poisoned");
+        if entries.get(url).is_some_and(|e| e.expires <= Instant::now()) {
+            entries.remove(url);
+        }
+        None
+    }
+
+    /// Store `value` under `url`, expiring after the duration implied by `ttl`,
+    /// evicting the least-recently-used entry first if the cache is full.
+    pub(crate) fn put(&self, url: Url, value: T, ttl: Ttl) -> Arc<T> {
+        let value = Arc::new(value);
+        let used = self.tick();
+        let mut entries = self.entries.write().expect("cache lock should not be poisoned");
+        if !entries.contains_key(&url) && entries.len() >= self.config.capacity.get() {
+            if let Some(victim) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used.load(Ordering::Relaxed))
+                .map(|(url, _)| url.clone())
+            {
+                entries.remove(&victim);
+            }
+        }
+        entries.insert(
+            url,
+            Entry {
+                expires: Instant::now() + self.config.duration(ttl),
+                used: AtomicU64::new(used),
+                value: Arc::clone(&value),
+            },
+        );
+        value
+    }
+
+    /// Advance and return the access clock.
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Fetch the value for `url` from the cache, or populate it by awaiting
+    /// `fetch` and caching the result under the given `ttl`.
+    pub(crate) async fn get_or_fetch<F, Fut, E>(
+        &self,
+        url: Url,
+        ttl: Ttl,
+        fetch: F,
+    ) -> Result<Arc<T>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        if let Some(hit) = self.get(&url) {
+            return Ok(hit);
+        }
+        let value = fetch().await?;
+        Ok(self.put(url, value, ttl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(capacity: usize) -> CacheConfig {
+        CacheConfig {
+            capacity: NonZeroUsize::new(capacity).unwrap(),
+            mutable_ttl: Duration::from_secs(60),
+            immutable_ttl: Duration::from_secs(60),
+        }
+    }
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_hit_and_miss() {
+        let cache = Cache::new(config(4));
+        assert!(cache.get(&url("https://example.test/a")).is_none());
+        cache.put(url("https://example.test/a"), 1u32, Ttl::Immutable);
+        assert_eq!(cache.get(&url("https://example.test/a")).as_deref(), Some(&1));
+        assert!(cache.get(&url("https://example.test/b")).is_none());
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = Cache::new(config(2));
+        cache.put(url("https://example.test/a"), 1u32, Ttl::Immutable);
+        cache.put(url("https://example.test/b"), 2u32, Ttl::Immutable);
+        // Access `a` so `b` becomes the least-recently-used entry.
+        assert_eq!(cache.get(&url("https://example.test/a")).as_deref(), Some(&1));
+        cache.put(url("https://example.test/c"), 3u32, Ttl::Immutable);
+        assert!(cache.get(&url("https://example.test/b")).is_none());
+        assert_eq!(cache.get(&url("https://example.test/a")).as_deref(), Some(&1));
+        assert_eq!(cache.get(&url("https://example.test/c")).as_deref(), Some(&3));
+    }
+
+    #[test]
+    fn test_distinct_ttls() {
+        let cfg = CacheConfig {
+            capacity: NonZeroUsize::new(4).unwrap(),
+            mutable_ttl: Duration::ZERO,
+            immutable_ttl: Duration::from_secs(60),
+        };
+        let cache = Cache::new(cfg);
+        cache.put(url("https://example.test/draft"), 1u32, Ttl::Mutable);
+        cache.put(url("https://example.test/release"), 2u32, Ttl::Immutable);
+        // The mutable entry expires immediately; the immutable one survives.
+        assert!(cache.get(&url("https://example.test/draft")).is_none());
+        assert_eq!(
+            cache.get(&url("https://example.test/release")).as_deref(),
+            Some(&2)
+        );
+    }
+
+    #[test]
+    fn test_expired_entry_is_dropped() {
+        let cfg = CacheConfig {
+            capacity: NonZeroUsize::new(4).unwrap(),
+            mutable_ttl: Duration::ZERO,
+            immutable_ttl: Duration::ZERO,
+        };
+        let cache = Cache::new(cfg);
+        cache.put(url("https://example.test/a"), 1u32, Ttl::Mutable);
+        assert!(cache.get(&url("https://example.test/a")).is_none());
+    }
+}
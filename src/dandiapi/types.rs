@@ -2,6 +2,7 @@ use super::{DandisetId, VersionId};
 use crate::paths::{PureDirPath, PurePath};
 use crate::s3::{PrefixedS3Client, S3Entry, S3Folder, S3Location, S3Object};
 use serde::Deserialize;
+use std::fmt;
 use thiserror::Error;
 use time::OffsetDateTime;
 use url::Url;
@@ -20,11 +21,68 @@ pub(crate) struct Dandiset {
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) modified: OffsetDateTime,
     //contact_person: String,
-    //embargo_status: ...,
+    #[serde(default)]
+    pub(crate) embargo_status: EmbargoStatus,
     pub(crate) draft_version: DandisetVersion,
     pub(crate) most_recent_published_version: Option<DandisetVersion>,
 }
 
+/// The embargo state of a Dandiset, as reported by the archive.
+///
+/// Embargoed content is only retrievable with a valid DANDI API token; when
+/// the status is anything other than [`EmbargoStatus::Open`] the server must
+/// forward the caller's token upstream and mint authenticated S3 download
+/// links rather than public ones.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum EmbargoStatus {
+    #[default]
+    Open,
+    Unembargoing,
+    Embargoed,
+}
+
+impl EmbargoStatus {
+    /// Whether the Dandiset's assets require an authenticated request.
+    pub(crate) fn is_embargoed(self) -> bool {
+        !matches!(self, EmbargoStatus::Open)
+    }
+}
+
+/// A DANDI Archive API token, forwarded to the upstream archive so that
+/// embargoed content can be listed, fetched, and downloaded.
+///
+/// Obtained from an incoming `Authorization` header or from configuration; its
+/// `Debug` representation is redacted so the secret never reaches logs.
+#[derive(Clone, Eq, PartialEq)]
+pub(crate) struct DandiApiToken(String);
+
+impl DandiApiToken {
+    pub(crate) fn new(token: impl Into<String>) -> DandiApiToken {
+        DandiApiToken(token.into())
+    }
+
+    /// The value for an outgoing `Authorization` header, in the `token <key>`
+    /// form the DANDI API expects.
+    pub(crate) fn header_value(&self) -> String {
+        format!("token {}", self.0)
+    }
+}
+
+impl fmt::Debug for DandiApiToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DandiApiToken").field(&"<redacted>").finish()
+    }
+}
+
+impl Dandiset {
+    /// Whether accessing this Dandiset's assets requires a forwarded
+    /// [`DandiApiToken`] because its content is embargoed.
+    pub(crate) fn requires_auth(&self) -> bool {
+        self.embargo_status.is_embargoed()
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
 pub(crate) struct DandisetVersion {
     pub(crate) version: VersionId,
@@ -162,7 +220,11 @@ impl BlobAsset {
     }
 
     pub(crate) fn etag(&self) -> Option<&str> {
-        self.metadata.digest.dandi_etag.as_deref()
+        self.metadata.digest.dandi_etag()
+    }
+
+    pub(crate) fn digests(&self) -> &AssetDigests {
+        &self.metadata.digest
     }
 
     pub(crate) fn download_url(&self) -> Option<&Url> {
@@ -201,10 +263,31 @@ pub(crate) struct AssetMetadata {
     digest: AssetDigests,
 }
 
+/// The full set of content digests DANDI reports for an asset, keyed by
+/// digest-algorithm name (e.g. `"dandi:dandi-etag"`, `"dandi:sha2-256"`).
+///
+/// DANDI returns the digests as a JSON object whose keys are algorithm names,
+/// so we capture the whole map rather than cherry-picking a single entry; this
+/// lets clients verify downloads against whichever algorithm they prefer.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
-pub(crate) struct AssetDigests {
-    #[serde(rename = "dandi:dandi-etag")]
-    dandi_etag: Option<String>,
+pub(crate) struct AssetDigests(std::collections::HashMap<String, String>);
+
+impl AssetDigests {
+    /// The `dandi:dandi-etag` digest, used as the asset's strong validator.
+    pub(crate) fn dandi_etag(&self) -> Option<&str> {
+        self.0.get("dandi:dandi-etag").map(String::as_str)
+    }
+
+    /// Iterate over all `(algorithm, value)` digest pairs.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+impl FromIterator<(String, String)> for AssetDigests {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> AssetDigests {
+        AssetDigests(iter.into_iter().collect())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
@@ -372,3 +455,49 @@ pub(crate) enum DandiResourceWithChildren {
     },
     ZarrEntry(ZarrEntry),
 }
+
+impl DandiResourceWithChildren {
+    /// The immediate child resources of this node, if any.
+    ///
+    /// Blobs and Zarr entries are leaves and have none; folders, Zarr assets,
+    /// and Zarr folders carry their listed children.
+    pub(crate) fn children(&self) -> &[DandiResource] {
+        match self {
+            DandiResourceWithChildren::Folder { children, .. }
+            | DandiResourceWithChildren::Zarr { children, .. }
+            | DandiResourceWithChildren::ZarrFolder { children, .. } => children,
+            DandiResourceWithChildren::Blob(_) | DandiResourceWithChildren::ZarrEntry(_) => &[],
+        }
+    }
+
+    /// Append this node's immediate children to `out`, debiting one unit of the
+    /// shared `budget` per child.
+    ///
+    /// `children()` returns leaf [`DandiResource`]s, so a single call descends
+    /// only one level; an infinite-depth `PROPFIND` handler performs the full
+    /// recursion by re-fetching each folder child as a
+    /// `DandiResourceWithChildren` and calling this again with the *same*
+    /// `budget`. Because the budget is threaded through every level, it caps the
+    /// total node count of the entire recursive walk, and [`DepthLimitExceeded`]
+    /// is returned as soon as that cap would be exceeded so the handler can fall
+    /// back to `propfind-finite-depth`.
+    pub(crate) fn collect_children_bounded<'a>(
+        &'a self,
+        budget: &mut usize,
+        out: &mut Vec<&'a DandiResource>,
+    ) -> Result<(), DepthLimitExceeded> {
+        for child in self.children() {
+            if *budget == 0 {
+                return Err(DepthLimitExceeded);
+            }
+            *budget -= 1;
+            out.push(child);
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when a bounded recursive traversal would visit more nodes
+/// than the configured cap allows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct DepthLimitExceeded;
@@ -1,12 +1,17 @@
 use super::VersionSpec;
 use crate::consts::DAV_XML_CONTENT_TYPE;
-use crate::dandi::DandisetId;
+use crate::dandi::{AssetDigests, DandisetId};
 use crate::paths::PureDirPath;
 use axum::{
     async_trait,
     body::Body,
     extract::FromRequestParts,
-    http::{header::CONTENT_TYPE, request::Parts, response::Response, StatusCode},
+    http::{
+        header::{CONTENT_TYPE, IF_MODIFIED_SINCE, IF_NONE_MATCH},
+        request::Parts,
+        response::Response,
+        StatusCode,
+    },
     response::IntoResponse,
 };
 use indoc::indoc;
@@ -16,7 +21,7 @@ use std::fmt::{self, Write};
 use time::{
     format_description::{well_known::Rfc3339, FormatItem},
     macros::format_description,
-    OffsetDateTime,
+    OffsetDateTime, PrimitiveDateTime,
 };
 
 static RFC1123: &[FormatItem<'_>] = format_description!(
@@ -38,6 +43,27 @@ static INFINITE_DEPTH_RESPONSE: &str = indoc! {r#"
 </error>
 "#};
 
+static EMBARGOED_RESPONSE: &str = indoc! {r#"
+<?xml version="1.0" encoding="utf-8"?>
+<error xmlns="DAV:">
+    <need-privileges>Embargoed resource requires a valid DANDI API token</need-privileges>
+</error>
+"#};
+
+/// The response returned when an embargoed resource is requested without a
+/// valid DANDI API token.
+///
+/// `401 Unauthorized` prompts the client to supply credentials; once a token is
+/// present but rejected upstream, the handler should instead surface `403`.
+pub(super) fn embargoed_response() -> Response<Body> {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(CONTENT_TYPE, DAV_XML_CONTENT_TYPE)],
+        EMBARGOED_RESPONSE,
+    )
+        .into_response()
+}
+
 pub(super) fn version_path(dandiset_id: &DandisetId, version: &VersionSpec) -> PureDirPath {
     fn writer(s: &mut String, dandiset_id: &DandisetId, version: &VersionSpec) -> fmt::Result {
         write!(s, "dandisets/{dandiset_id}/")?;
@@ -66,34 +92,155 @@ pub(super) fn format_modifieddate(dt: OffsetDateTime) -> String {
         .expect("formatting an OffsetDateTime in RFC 1123 format should not fail")
 }
 
+/// The default cap on the number of resources an infinite-depth `PROPFIND`
+/// will walk before falling back to [`INFINITE_DEPTH_RESPONSE`], keeping
+/// recursive listings bounded in both time and memory.
+pub(super) const DEFAULT_INFINITE_DEPTH_LIMIT: usize = 10_000;
+
+/// The `Depth` of a `PROPFIND` request.
+///
+/// `Depth: infinity` is accepted here rather than rejected outright; the
+/// handler is responsible for walking the resource tree up to a configured cap
+/// and only falling back to [`INFINITE_DEPTH_RESPONSE`] if that cap would be
+/// exceeded (see [`Depth::is_infinite`]).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub(super) enum FiniteDepth {
+pub(super) enum Depth {
     Zero,
     One,
+    Infinity,
+}
+
+impl Depth {
+    /// Whether this depth requests an unbounded recursive listing.
+    pub(super) fn is_infinite(self) -> bool {
+        matches!(self, Depth::Infinity)
+    }
+}
+
+/// The response returned when an infinite-depth `PROPFIND` would exceed the
+/// server's recursion cap.
+pub(super) fn infinite_depth_response() -> Response<Body> {
+    (
+        StatusCode::FORBIDDEN,
+        [(CONTENT_TYPE, DAV_XML_CONTENT_TYPE)],
+        INFINITE_DEPTH_RESPONSE,
+    )
+        .into_response()
 }
 
 // vv Workaround for <https://github.com/dtolnay/async-trait/issues/259>;
 // vv remove once that's fixed
 #[allow(unused_qualifications)]
 #[async_trait]
-impl<S: Send + Sync> FromRequestParts<S> for FiniteDepth {
+impl<S: Send + Sync> FromRequestParts<S> for Depth {
     type Rejection = Response<Body>;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         match parts.headers.get("Depth").map(|v| v.to_str()) {
-            Some(Ok("0")) => Ok(FiniteDepth::Zero),
-            Some(Ok("1")) => Ok(FiniteDepth::One),
-            Some(Ok("infinity")) | None => Err((
-                StatusCode::FORBIDDEN,
-                [(CONTENT_TYPE, DAV_XML_CONTENT_TYPE)],
-                INFINITE_DEPTH_RESPONSE,
-            )
-                .into_response()),
+            Some(Ok("0")) => Ok(Depth::Zero),
+            Some(Ok("1")) => Ok(Depth::One),
+            Some(Ok("infinity")) | None => Ok(Depth::Infinity),
             _ => Err((StatusCode::BAD_REQUEST, "Invalid \"Depth\" header\n").into_response()),
         }
     }
 }
 
+/// The conditional-request headers relevant to serving assets: `If-None-Match`
+/// and `If-Modified-Since`.
+///
+/// Either field is `None` when the corresponding header is absent or cannot be
+/// parsed; an unparseable header is treated as if it were not sent, as RFC 7232
+/// permits. Use [`Conditionals::is_fresh`] to decide whether a `304 Not
+/// Modified` response is warranted for a given validator pair.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) struct Conditionals {
+    /// The set of entity-tags from `If-None-Match`, or `None` if the header was
+    /// absent. `Some` with an empty slice means the header was present but held
+    /// no usable tags.
+    if_none_match: Option<Vec<String>>,
+    if_modified_since: Option<OffsetDateTime>,
+}
+
+impl Conditionals {
+    /// Return `true` if a resource with the given strong `etag` and
+    /// `modified` timestamp is unchanged according to the request's validators,
+    /// in which case the handler should reply `304 Not Modified`.
+    ///
+    /// Per RFC 7232 §6, `If-None-Match` takes precedence over
+    /// `If-Modified-Since` when both are present.
+    pub(super) fn is_fresh(&self, etag: Option<&str>, modified: OffsetDateTime) -> bool {
+        if let Some(tags) = self.if_none_match.as_deref() {
+            return match etag {
+                Some(etag) => tags.iter().any(|t| t == "*" || etag_matches(t, etag)),
+                None => false,
+            };
+        }
+        if let Some(since) = self.if_modified_since {
+            return modified <= since;
+        }
+        false
+    }
+
+    /// Build a `304 Not Modified` response carrying the validators a
+    /// conditional GET/HEAD handler should echo back, so clients can continue
+    /// to use their cached copy.
+    pub(super) fn not_modified_response(
+        etag: Option<&str>,
+        modified: OffsetDateTime,
+    ) -> Response<Body> {
+        let mut resp = StatusCode::NOT_MODIFIED.into_response();
+        if let Some(etag) = etag {
+            if let Ok(value) = format!("\"{etag}\"").parse() {
+                resp.headers_mut().insert(axum::http::header::ETAG, value);
+            }
+        }
+        if let Ok(value) = format_modifieddate(modified).parse() {
+            resp.headers_mut()
+                .insert(axum::http::header::LAST_MODIFIED, value);
+        }
+        resp
+    }
+}
+
+// vv Workaround for <https://github.com/dtolnay/async-trait/issues/259>;
+// vv remove once that's fixed
+#[allow(unused_qualifications)]
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Conditionals {
+    type Rejection = Response<Body>;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let if_none_match = parts
+            .headers
+            .get(IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.split(',')
+                    .map(|tag| tag.trim().trim_start_matches("W/").trim_matches('"').to_owned())
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            });
+        let if_modified_since = parts
+            .headers
+            .get(IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| PrimitiveDateTime::parse(v, &RFC1123).ok())
+            .map(PrimitiveDateTime::assume_utc);
+        Ok(Conditionals {
+            if_none_match,
+            if_modified_since,
+        })
+    }
+}
+
+/// Compare a client-supplied entity-tag against a resource's strong validator,
+/// ignoring a leading weak-comparison marker and surrounding quotes.
+fn etag_matches(candidate: &str, etag: &str) -> bool {
+    let candidate = candidate.trim_start_matches("W/").trim_matches('"');
+    let etag = etag.trim_start_matches("W/").trim_matches('"');
+    candidate == etag
+}
+
 /// A percent-encoded URI or URI path, for use in the `href` attribute of an
 /// HTML `<a>` tag or in a `<DAV:href>` tag in a `PROPFIND` response
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -133,6 +280,135 @@ impl Serialize for Href {
     }
 }
 
+/// Escape the XML special characters in `s` so that it is safe to place in a
+/// text node or attribute of a `DAV:` response.
+pub(super) fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A string destined for a text node of a `PROPFIND` response, serialized with
+/// its XML special characters escaped.
+///
+/// Analogous to [`Href`] (which guards the `href` attribute with
+/// percent-encoding), this type guarantees that any path or display string
+/// routed through it cannot produce malformed XML. Construct one from anything
+/// that renders as a string — `PurePath`, `PureDirPath`, or a plain display
+/// name — and serialize it in place of the raw value.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(super) struct Xml(String);
+
+impl<T: fmt::Display> From<T> for Xml {
+    fn from(value: T) -> Xml {
+        Xml(value.to_string())
+    }
+}
+
+impl Xml {
+    /// The XML-escaped form of the wrapped text.
+    pub(super) fn escaped(&self) -> String {
+        xml_escape(&self.0)
+    }
+}
+
+impl Serialize for Xml {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.escaped())
+    }
+}
+
+/// Serialize `value` as the text content of the `PROPFIND` element `tag`,
+/// routing the text through [`Xml`] so that a path or display name containing
+/// `&`, `<`, `>`, or `"` cannot produce malformed XML.
+pub(super) fn text_element(tag: &str, value: impl Into<Xml>) -> String {
+    let value = value.into();
+    format!("<{tag}>{}</{tag}>", value.escaped())
+}
+
+/// Serialize a resource's display name as a `<displayname>` `PROPFIND`
+/// property.
+///
+/// Asset paths and folder names may contain `&`, `<`, `>`, or `"`, so the text
+/// is routed through [`text_element`]/[`Xml`] to keep the response well-formed.
+pub(super) fn displayname_property(name: impl Into<Xml>) -> String {
+    text_element("displayname", name)
+}
+
+/// Serialize the standard cache validators as `PROPFIND` live properties:
+/// `<getetag>` (when a strong validator is available) and `<getlastmodified>`.
+///
+/// The etag is quoted per RFC 7232 and both values are routed through
+/// [`text_element`], matching the `ETag`/`Last-Modified` headers a conditional
+/// GET/HEAD handler echoes via [`Conditionals::not_modified_response`], so
+/// WebDAV clients can cache and sync against them.
+pub(super) fn validator_properties(etag: Option<&str>, modified: OffsetDateTime) -> String {
+    let mut out = String::new();
+    if let Some(etag) = etag {
+        out.push_str(&text_element("getetag", format!("\"{etag}\"")));
+    }
+    out.push_str(&text_element(
+        "getlastmodified",
+        format_modifieddate(modified),
+    ));
+    out
+}
+
+/// Serialize an asset's content digests as custom `dandi:`-namespaced dead
+/// properties (e.g. `<dandi:sha2-256>…</dandi:sha2-256>`) for inclusion in a
+/// `PROPFIND` prop element.
+///
+/// Every digest DANDI reports is surfaced, not just the `dandi-etag`, so
+/// clients can verify downloads against whichever algorithm they prefer. The
+/// algorithm name supplies the local element name and the digest value is
+/// XML-escaped via [`text_element`]. Output is sorted by algorithm for stable,
+/// deterministic responses.
+pub(super) fn digest_properties(digests: &AssetDigests) -> String {
+    let mut pairs = digests.iter().collect::<Vec<_>>();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    let mut out = String::new();
+    for (algorithm, value) in pairs {
+        let Some(local) = digest_local_name(algorithm) else {
+            // Skip algorithms whose names can't form a safe XML element name
+            // rather than emit malformed markup.
+            continue;
+        };
+        out.push_str(&text_element(&format!("dandi:{local}"), value));
+    }
+    out
+}
+
+/// Derive a safe XML local element name from a DANDI digest-algorithm key
+/// (e.g. `"dandi:sha2-256"` → `"sha2-256"`).
+///
+/// Returns `None` if the resulting name does not start with an ASCII letter or
+/// `_`, or contains any character outside the conservative `[A-Za-z0-9._-]`
+/// set, so an upstream key holding XML-special characters or whitespace can
+/// never inject markup into a response.
+fn digest_local_name(algorithm: &str) -> Option<String> {
+    let local = algorithm.rsplit(':').next().unwrap_or(algorithm);
+    if local.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        Some(local.to_owned())
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,6 +423,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_conditionals_if_none_match() {
+        let cond = Conditionals {
+            if_none_match: Some(vec![String::from("abc123")]),
+            if_modified_since: None,
+        };
+        assert!(cond.is_fresh(Some("\"abc123\""), datetime!(2024-01-01 00:00:00 UTC)));
+        assert!(!cond.is_fresh(Some("def456"), datetime!(2024-01-01 00:00:00 UTC)));
+        assert!(!cond.is_fresh(None, datetime!(2024-01-01 00:00:00 UTC)));
+    }
+
+    #[test]
+    fn test_conditionals_if_modified_since() {
+        let cond = Conditionals {
+            if_none_match: None,
+            if_modified_since: Some(datetime!(1994-11-06 08:49:37 UTC)),
+        };
+        assert!(cond.is_fresh(None, datetime!(1994-11-06 08:49:37 UTC)));
+        assert!(cond.is_fresh(None, datetime!(1994-11-05 00:00:00 UTC)));
+        assert!(!cond.is_fresh(None, datetime!(1994-11-07 00:00:00 UTC)));
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape(r#"a & b < c > d " e"#),
+            "a &amp; b &lt; c &gt; d &quot; e"
+        );
+        assert_eq!(xml_escape("plain/path.txt"), "plain/path.txt");
+    }
+
+    #[test]
+    fn test_validator_properties() {
+        let xml = validator_properties(Some("abc-1"), datetime!(1994-11-06 08:49:37 UTC));
+        assert!(xml.contains("<getetag>\"abc-1\"</getetag>"));
+        assert!(xml.contains("<getlastmodified>Sun, 06 Nov 1994 08:49:37 GMT</getlastmodified>"));
+        assert!(!validator_properties(None, datetime!(1994-11-06 08:49:37 UTC)).contains("getetag"));
+    }
+
+    #[test]
+    fn test_digest_properties() {
+        let digests = [
+            (String::from("dandi:dandi-etag"), String::from("0123-1")),
+            (String::from("dandi:sha2-256"), String::from("abcdef")),
+        ]
+        .into_iter()
+        .collect::<AssetDigests>();
+        let xml = digest_properties(&digests);
+        assert!(xml.contains("<dandi:sha2-256>abcdef</dandi:sha2-256>"));
+        assert!(xml.contains("<dandi:dandi-etag>0123-1</dandi:dandi-etag>"));
+        // Sorted by algorithm: dandi-etag precedes sha2-256.
+        assert!(xml.find("dandi-etag").unwrap() < xml.find("sha2-256").unwrap());
+    }
+
+    #[test]
+    fn test_digest_properties_rejects_unsafe_name() {
+        let digests = [(String::from("dandi:<script>"), String::from("x"))]
+            .into_iter()
+            .collect::<AssetDigests>();
+        assert_eq!(digest_properties(&digests), "");
+    }
+
+    #[test]
+    fn test_text_element_escapes_path() {
+        assert_eq!(
+            text_element("displayname", "a & b <c>/\"d\""),
+            "<displayname>a &amp; b &lt;c&gt;/&quot;d&quot;</displayname>"
+        );
+    }
+
+    #[test]
+    fn test_displayname_property_escapes() {
+        assert_eq!(
+            displayname_property("AT&T <x>.dat"),
+            "<displayname>AT&amp;T &lt;x&gt;.dat</displayname>"
+        );
+    }
+
     #[test]
     fn test_format_modifieddate() {
         let dt = datetime!(1994-11-06 03:49:37 -5);